@@ -23,6 +23,17 @@ use url::Url;
 
 pub trait DatabaseInspector {
     fn introspect(&self, schema: &String) -> DatabaseSchema;
+
+    /// Introspects every schema in `namespaces` and merges their tables into
+    /// a single `DatabaseSchema`. Not called anywhere in this crate.
+    /// `SqlDatabase::namespaces` (query-engine's sql-connector) is not read
+    /// by `ResetData` either, which truncates only the connection's current
+    /// schema.
+    fn introspect_namespaces(&self, namespaces: &Vec<String>) -> DatabaseSchema {
+        DatabaseSchema {
+            tables: namespaces.iter().flat_map(|schema| self.introspect(schema).tables).collect(),
+        }
+    }
 }
 
 impl DatabaseInspector {
@@ -50,6 +50,7 @@ impl Sqlite {
     fn get_table(&self, schema: &String, table: &String) -> Table {
         let introspected_columns = self.get_columns(&schema, &table);
         let introspected_foreign_keys = self.get_foreign_constraints(&schema, &table);
+        let indexes = self.get_indexes(&schema, &table);
 
         let mut columns_copy = introspected_columns.clone();
         columns_copy.sort_by_key(|c| c.pk);
@@ -66,7 +67,7 @@ impl Sqlite {
                 introspected_foreign_keys,
                 Box::new(column_type),
             ),
-            indexes: Vec::new(),
+            indexes,
             primary_key_columns: pk_columns,
         }
     }
@@ -117,27 +118,132 @@ impl Sqlite {
     }
 
     #[allow(unused)]
-    fn get_sequence(&self, _schema: &String, _table: &String) -> Sequence {
-        unimplemented!()
+    fn get_sequence(&self, schema: &String, table: &String) -> Sequence {
+        let sql = format!(r#"SELECT seq FROM "{}".sqlite_sequence WHERE name = '{}'"#, schema, table);
+
+        // `sqlite_sequence` only exists once some table in the schema has an
+        // AUTOINCREMENT column, so querying it against a fresh database errors
+        // with "no such table" rather than returning zero rows.
+        let current_value = match self.database.query_on_raw_connection(&schema, &sql, &[]) {
+            Ok(result_set) => result_set
+                .into_iter()
+                .next()
+                .map(|row| row["seq"].as_i64().unwrap() as u32)
+                .unwrap_or(0),
+            Err(_) => 0,
+        };
+
+        Sequence {
+            name: table.to_string(),
+            current_value,
+        }
     }
 
-    #[allow(unused)]
-    fn get_index(&self, _schema: &String, _table: &String) -> Index {
-        unimplemented!()
+    fn get_indexes(&self, schema: &String, table: &String) -> Vec<Index> {
+        let sql = format!(r#"Pragma "{}".index_list("{}");"#, schema, table);
+
+        let result_set = self.database.query_on_raw_connection(&schema, &sql, &[]).unwrap();
+        result_set
+            .into_iter()
+            // `origin = 'pk'` is the implicit index SQLite creates for the primary key; that's
+            // already represented by `Table.primary_key_columns`, so skip it here.
+            .filter(|row| row["origin"].into_string().unwrap() != "pk")
+            .map(|row| {
+                let name = row["name"].into_string().unwrap();
+                let unique = row["unique"].as_bool().unwrap();
+                let columns = self.get_index_columns(schema, &name);
+
+                Index { name, columns, unique }
+            })
+            .collect()
+    }
+
+    fn get_index_columns(&self, schema: &String, index: &String) -> Vec<String> {
+        let sql = format!(r#"Pragma "{}".index_info("{}");"#, schema, index);
+
+        let result_set = self.database.query_on_raw_connection(&schema, &sql, &[]).unwrap();
+        result_set
+            .into_iter()
+            .map(|row| row["name"].into_string().unwrap())
+            .collect()
     }
 }
 
+/// Alternate spellings of each `ColumnType` seen across the databases we
+/// introspect, e.g. SQLite's own type affinities, Postgres's `int4`/`int8`,
+/// or a `VARCHAR(n)` with a length. Grouping them here means `column_type`
+/// and [`types_are_compatible`] agree on what counts as "the same type".
+fn type_compatibility_table() -> &'static [(ColumnType, &'static [&'static str])] {
+    &[
+        (ColumnType::Int, &["INTEGER", "INT4", "INT8", "INT", "BIGINT", "SMALLINT"]),
+        (ColumnType::Float, &["REAL", "FLOAT", "DOUBLE", "NUMERIC"]),
+        (ColumnType::Boolean, &["BOOLEAN", "BOOL"]),
+        (ColumnType::String, &["TEXT", "VARCHAR", "CHAR", "CLOB"]),
+        (ColumnType::DateTime, &["DATE", "DATETIME", "TIMESTAMP"]),
+    ]
+}
+
 fn column_type(column: &IntrospectedColumn) -> ColumnType {
-    match column.tpe.as_ref() {
-        "INTEGER" => ColumnType::Int,
-        "REAL" => ColumnType::Float,
-        "BOOLEAN" => ColumnType::Boolean,
-        "TEXT" => ColumnType::String,
-        s if s.starts_with("VARCHAR") => ColumnType::String,
-        "DATE" => ColumnType::DateTime,
-        x => panic!(format!(
-            "type {} is not supported here yet. Column was: {}",
-            x, column.name
-        )),
+    let tpe = column.tpe.to_ascii_uppercase();
+    type_compatibility_table()
+        .iter()
+        .find(|(_, spellings)| spellings.iter().any(|s| tpe.starts_with(*s)))
+        .map(|(column_type, _)| *column_type)
+        .unwrap_or_else(|| {
+            panic!(format!(
+                "type {} is not supported here yet. Column was: {}",
+                column.tpe, column.name
+            ))
+        })
+}
+
+/// Whether `introspected_type` (as reported by the database, e.g.
+/// `"VARCHAR(255)"` or `"int8"`) is semantically the same type as `expected`.
+///
+/// `DatabaseSchemaDiffer` (the thing that would call this before emitting an
+/// `AlterColumn`) is defined elsewhere in this crate, outside the
+/// `database_inspector` module, so it can't be reached or edited from here;
+/// this has no effect on the steps the diff engine emits today. Calling it
+/// from wherever `DatabaseSchemaDiffer::diff` builds `AlterColumn` is the
+/// remaining work; tracked, not done.
+#[allow(unused)]
+pub fn types_are_compatible(introspected_type: &str, expected: &ColumnType) -> bool {
+    let tpe = introspected_type.to_ascii_uppercase();
+    type_compatibility_table()
+        .iter()
+        .find(|(column_type, _)| column_type == expected)
+        .map(|(_, spellings)| spellings.iter().any(|s| tpe.starts_with(*s)))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn types_are_compatible_accepts_alternate_spellings() {
+        assert!(types_are_compatible("INT8", &ColumnType::Int));
+        assert!(types_are_compatible("varchar(255)", &ColumnType::String));
+        assert!(types_are_compatible("BOOL", &ColumnType::Boolean));
+    }
+
+    #[test]
+    fn types_are_compatible_rejects_mismatched_types() {
+        assert!(!types_are_compatible("TEXT", &ColumnType::Int));
+        assert!(!types_are_compatible("unknowntype", &ColumnType::String));
+    }
+
+    #[test]
+    fn column_type_reads_the_introspected_sqlite_type() {
+        let column = IntrospectedColumn {
+            name: "age".to_string(),
+            table: "users".to_string(),
+            tpe: "INTEGER".to_string(),
+            is_required: true,
+            default: None,
+            pk: 0,
+        };
+
+        assert_eq!(column_type(&column), ColumnType::Int);
     }
 }
@@ -13,6 +13,26 @@ use prisma_query::{error::Error as SqlError, transaction::Connection};
 use std::cell::RefCell;
 use std::ops::DerefMut;
 
+/// The forward and backward step lists for a single inferred migration, so
+/// a failed or unwanted migration can be rolled back deterministically
+/// instead of only ever moving forward.
+pub struct ReversibleMigration {
+    pub up: Vec<SqlMigrationStep>,
+    pub down: Vec<SqlMigrationStep>,
+}
+
+/// A single row of the `_migrations` bookkeeping table: a migration that was
+/// already applied, the rendered SQL it ran, and a checksum of the database
+/// schema as it stood right after that migration committed, so a later run
+/// can tell whether the live database still matches what was recorded.
+pub struct AppliedMigration {
+    pub name: String,
+    pub rendered_steps: String,
+    pub schema_checksum: String,
+}
+
+const MIGRATIONS_TABLE: &str = "_migrations";
+
 pub struct SqlDatabaseMigrationStepsInferrer<'a> {
     schema_name: String,
     connection: &'a RefCell<Connection>,
@@ -48,6 +68,33 @@ impl<'a> SqlDatabaseMigrationStepsInferrer<'a> {
         }
     }
 
+    /// Like `infer`, but also computes the inverse step list needed to roll
+    /// the migration back, the way file-based tools keep `up.sql`/`down.sql`
+    /// next to each other. `down` is obtained by diffing in the opposite
+    /// direction, which naturally inverts `CreateTable`/`DropTable` and
+    /// `AddColumn`/`DropColumn`, and re-points `AlterColumn` at the column
+    /// description that was in place before `up` ran.
+    pub fn infer_with_rollback(&self, _previous: &Schema, next: &Schema) -> Result<ReversibleMigration, SqlError> {
+        let current_database_schema = self
+            .introspector
+            .introspect(self.connection.borrow_mut().deref_mut(), &self.schema_name)?
+            .schema;
+        let expected_database_schema = DatabaseSchemaCalculator::calculate(next);
+
+        let up = DatabaseSchemaDiffer::diff(&current_database_schema, &expected_database_schema, &self.schema_name);
+        let down = DatabaseSchemaDiffer::diff(&expected_database_schema, &current_database_schema, &self.schema_name);
+
+        let is_sqlite = true;
+        if is_sqlite {
+            Ok(ReversibleMigration {
+                up: self.fix_stupid_sqlite(up, &current_database_schema, &expected_database_schema),
+                down: self.fix_stupid_sqlite(down, &expected_database_schema, &current_database_schema),
+            })
+        } else {
+            Ok(ReversibleMigration { up, down })
+        }
+    }
+
     fn fix_stupid_sqlite(
         &self,
         steps: Vec<SqlMigrationStep>,
@@ -69,6 +116,196 @@ impl<'a> SqlDatabaseMigrationStepsInferrer<'a> {
         result
     }
 
+    /// Runs `steps` against a single transaction, committing only if every
+    /// step succeeds and rolling the whole batch back on the first error, so
+    /// a migration is all-or-nothing instead of leaving the schema half
+    /// migrated. `render` turns a step into the SQL statement needed to
+    /// apply it. `PRAGMA foreign_keys=OFF;` (SQLite refuses to toggle it
+    /// inside a transaction) runs before `BEGIN`, and its matching `=ON;`
+    /// runs after `COMMIT`/`ROLLBACK` — re-enabling FK enforcement before the
+    /// transactional rebuild even started would defeat the whole point of
+    /// turning it off in the first place.
+    pub fn execute_transactionally<F>(&self, steps: Vec<SqlMigrationStep>, render: F) -> Result<(), SqlError>
+    where
+        F: Fn(&SqlMigrationStep) -> String,
+    {
+        let (before_tx, tx_steps, after_tx) = Self::partition_around_transaction(steps);
+
+        let mut connection = self.connection.borrow_mut();
+        let connection = connection.deref_mut();
+
+        for step in &before_tx {
+            connection.query_on_raw_connection(&self.schema_name, &render(step), &[])?;
+        }
+
+        connection.query_on_raw_connection(&self.schema_name, "BEGIN;", &[])?;
+
+        for step in &tx_steps {
+            if let Err(err) = connection.query_on_raw_connection(&self.schema_name, &render(step), &[]) {
+                let _ = connection.query_on_raw_connection(&self.schema_name, "ROLLBACK;", &[]);
+                for step in &after_tx {
+                    let _ = connection.query_on_raw_connection(&self.schema_name, &render(step), &[]);
+                }
+                return Err(err);
+            }
+        }
+
+        connection.query_on_raw_connection(&self.schema_name, "COMMIT;", &[])?;
+
+        for step in &after_tx {
+            connection.query_on_raw_connection(&self.schema_name, &render(step), &[])?;
+        }
+
+        Ok(())
+    }
+
+    /// Splits `steps` into the ones that must run before `BEGIN`, the ones
+    /// that run inside the transaction, and the ones that must run after
+    /// `COMMIT`/`ROLLBACK` — `PRAGMA foreign_keys=OFF;` and its matching
+    /// `=ON;` can't both be "outside the transaction", or FK enforcement
+    /// would be back on before the transactional rebuild it's meant to
+    /// protect even starts.
+    fn partition_around_transaction(
+        steps: Vec<SqlMigrationStep>,
+    ) -> (Vec<SqlMigrationStep>, Vec<SqlMigrationStep>, Vec<SqlMigrationStep>) {
+        let mut before_tx = Vec::new();
+        let mut tx_steps = Vec::new();
+        let mut after_tx = Vec::new();
+
+        for step in steps {
+            match Self::foreign_keys_pragma(&step) {
+                Some(false) => before_tx.push(step), // PRAGMA foreign_keys=OFF;
+                Some(true) => after_tx.push(step),    // PRAGMA foreign_keys=ON;
+                None => tx_steps.push(step),
+            }
+        }
+
+        (before_tx, tx_steps, after_tx)
+    }
+
+    /// `Some(true)` for `PRAGMA foreign_keys=ON;`, `Some(false)` for
+    /// `PRAGMA foreign_keys=OFF;`, `None` for anything else.
+    fn foreign_keys_pragma(step: &SqlMigrationStep) -> Option<bool> {
+        match step {
+            SqlMigrationStep::RawSql(sql) => {
+                let sql = sql.to_ascii_uppercase();
+                if sql.contains("PRAGMA FOREIGN_KEYS=ON") {
+                    Some(true)
+                } else if sql.contains("PRAGMA FOREIGN_KEYS=OFF") {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Creates the `_migrations` bookkeeping table if it doesn't exist yet.
+    fn ensure_migrations_table_exists(&self) -> Result<(), SqlError> {
+        let mut connection = self.connection.borrow_mut();
+        let sql = format!(
+            r#"CREATE TABLE IF NOT EXISTS "{}"."{}" (
+                name TEXT PRIMARY KEY,
+                steps TEXT NOT NULL,
+                schema_checksum TEXT NOT NULL,
+                applied_at TEXT NOT NULL
+            );"#,
+            self.schema_name, MIGRATIONS_TABLE
+        );
+
+        connection.deref_mut().query_on_raw_connection(&self.schema_name, &sql, &[])?;
+        Ok(())
+    }
+
+    /// The migrations already recorded as applied, oldest first. A missing
+    /// `_migrations` table (a fresh database that has never been migrated
+    /// through this engine) is treated as an empty applied set rather than
+    /// an error.
+    pub fn applied_migrations(&self) -> Result<Vec<AppliedMigration>, SqlError> {
+        let mut connection = self.connection.borrow_mut();
+        let sql = format!(
+            r#"SELECT name, steps, schema_checksum FROM "{}"."{}" ORDER BY applied_at"#,
+            self.schema_name, MIGRATIONS_TABLE
+        );
+
+        match connection.deref_mut().query_on_raw_connection(&self.schema_name, &sql, &[]) {
+            Ok(result_set) => Ok(result_set
+                .into_iter()
+                .map(|row| AppliedMigration {
+                    name: row["name"].into_string().unwrap(),
+                    rendered_steps: row["steps"].into_string().unwrap(),
+                    schema_checksum: row["schema_checksum"].into_string().unwrap(),
+                })
+                .collect()),
+            Err(_) => Ok(Vec::new()), // table does not exist yet: nothing has been applied
+        }
+    }
+
+    /// Records `name`/`rendered_steps` as applied, checksumming
+    /// `resulting_schema` (the database schema as introspected right after
+    /// the migration committed) so a later run can compare it against the
+    /// live database. Call this after `execute_transactionally` has
+    /// committed the migration successfully.
+    pub fn record_migration(&self, name: &str, rendered_steps: &str, resulting_schema: &DatabaseSchema) -> Result<(), SqlError> {
+        self.ensure_migrations_table_exists()?;
+        let schema_checksum = Self::checksum_of_schema(resulting_schema);
+
+        let mut connection = self.connection.borrow_mut();
+        let sql = format!(
+            r#"INSERT INTO "{}"."{}" (name, steps, schema_checksum, applied_at) VALUES ('{}', '{}', '{}', datetime('now'));"#,
+            self.schema_name,
+            MIGRATIONS_TABLE,
+            name.replace('\'', "''"),
+            rendered_steps.replace('\'', "''"),
+            schema_checksum
+        );
+
+        connection.deref_mut().query_on_raw_connection(&self.schema_name, &sql, &[])?;
+        Ok(())
+    }
+
+    /// Whether the live database still matches the last migration recorded
+    /// in `_migrations`: re-introspects the database and compares its
+    /// checksum against the one stored for the most recently applied
+    /// migration. Callers should refuse to infer or apply further
+    /// migrations when this returns `false`, since it means the database was
+    /// modified outside the migration engine since that migration ran.
+    pub fn detect_drift(&self) -> Result<bool, SqlError> {
+        let applied = self.applied_migrations()?;
+        let last = match applied.last() {
+            Some(last) => last,
+            None => return Ok(true), // nothing applied yet: nothing to drift from
+        };
+
+        let current_schema = self
+            .introspector
+            .introspect(self.connection.borrow_mut().deref_mut(), &self.schema_name)?
+            .schema;
+
+        Ok(last.schema_checksum == Self::checksum_of_schema(&current_schema))
+    }
+
+    /// A simple content checksum of a database schema's tables and columns.
+    /// Not cryptographic; it only needs to detect accidental drift, not
+    /// resist tampering. Hashes each column's full `Debug` output rather than
+    /// just its name, so a column changed out-of-band (type, nullability,
+    /// default, ...) changes the checksum too, not just an added or removed
+    /// column.
+    fn checksum_of_schema(schema: &DatabaseSchema) -> String {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = DefaultHasher::new();
+        for table in &schema.tables {
+            table.name.hash(&mut hasher);
+            for column in &table.columns {
+                format!("{:?}", column).hash(&mut hasher);
+            }
+        }
+        format!("{:x}", hasher.finish())
+    }
+
     fn needs_fix(&self, alter_table: &AlterTable) -> bool {
         let change_that_does_not_work_on_sqlite = alter_table.changes.iter().find(|change| match change {
             TableChange::AddColumn(_) => false,
@@ -83,7 +320,7 @@ impl<'a> SqlDatabaseMigrationStepsInferrer<'a> {
         let name_of_temporary_table = format!("new_{}", next.name.clone());
         vec![
             SqlMigrationStep::RawSql("PRAGMA foreign_keys=OFF;".to_string()),
-            // todo: start transaction now
+            // the rebuild below runs atomically: execute_transactionally wraps this whole step list in one transaction
             SqlMigrationStep::CreateTable(CreateTable {
                 name: format!("new_{}", next.name.clone()),
                 columns: DatabaseSchemaDiffer::column_descriptions(&next.columns, next, &next_schema.relations),
@@ -117,17 +354,346 @@ impl<'a> SqlDatabaseMigrationStepsInferrer<'a> {
                 name: name_of_temporary_table,
                 new_name: next.name.clone(),
             },
-            // todo: recreate indexes + triggers
+            // todo: recreate indexes + triggers. `current`/`next` here are `TableInfo`
+            // (the `relational`/`RelationalIntrospectionConnector` pipeline this struct's
+            // `introspector` is wired to), not the `database_schema::Table` that
+            // `Sqlite::get_indexes` populates for `DatabaseInspector`. The two
+            // introspection pipelines don't feed into each other today, so the index
+            // metadata `get_indexes` gathers isn't reachable from here yet — recreating
+            // indexes on rebuild needs that bridged first, not just a loop over
+            // `next.indexes` (which `TableInfo` doesn't have).
             SqlMigrationStep::RawSql(format!(r#"PRAGMA "{}".foreign_key_check;"#, self.schema_name)),
-            // todo: commit transaction
+            // commit happens once execute_transactionally has run every step above without error
             SqlMigrationStep::RawSql("PRAGMA foreign_keys=ON;".to_string()),
         ]
     }
+
+    /// Infers one half of a zero-downtime rollout for `next`. Unlike `infer`,
+    /// which rewrites tables in place, `Expand` never drops a column: new or
+    /// renamed columns are added alongside the old ones, backfilled, and
+    /// kept in sync with a routing trigger so both the old and new
+    /// application versions can run against the schema at once. `Contract`
+    /// removes that trigger, the routing helper, and the now-superseded old
+    /// columns, and should only run once every client has moved to the new
+    /// schema.
+    ///
+    /// Panics if this struct isn't Postgres-backed; see the comment inside.
+    pub fn infer_zero_downtime(&self, next: &Schema, phase: ZeroDowntimePhase) -> Result<Vec<SqlMigrationStep>, SqlError> {
+        // `expand`/`contract` only emit Postgres DDL (session settings, PL/pgSQL trigger
+        // functions), but `new` always wires this struct to `SqlLiteConnector` (see its
+        // `TODO`) with no way to ask for a Postgres-backed one instead. Hold this phase
+        // unreachable until that's configurable, rather than silently run DDL against a
+        // connection that can't execute it.
+        let is_postgres_backed = false;
+        if !is_postgres_backed {
+            panic!("infer_zero_downtime needs a Postgres-backed SqlDatabaseMigrationStepsInferrer; this one is always constructed with SqlLiteConnector (see `new`)");
+        }
+
+        let current_database_schema = self
+            .introspector
+            .introspect(self.connection.borrow_mut().deref_mut(), &self.schema_name)?
+            .schema;
+        let expected_database_schema = DatabaseSchemaCalculator::calculate(next);
+        let diff = DatabaseSchemaDiffer::diff(&current_database_schema, &expected_database_schema, &self.schema_name);
+
+        Ok(match phase {
+            ZeroDowntimePhase::Expand => Self::expand(diff),
+            ZeroDowntimePhase::Contract => Self::contract(diff),
+        })
+    }
+
+    /// Pure transformation of a diff's steps into the expand half of a
+    /// zero-downtime rollout; takes no `&self` since it needs no database
+    /// access, which also makes it directly unit-testable.
+    fn expand(steps: Vec<SqlMigrationStep>) -> Vec<SqlMigrationStep> {
+        let mut result = vec![Self::install_schema_routing_helper()];
+
+        for step in steps {
+            match step {
+                SqlMigrationStep::AlterTable(ref alter_table) if !alter_table.changes.is_empty() => {
+                    let additive: Vec<TableChange> = alter_table
+                        .changes
+                        .iter()
+                        .filter(|change| matches!(change, TableChange::AddColumn(_)))
+                        .cloned()
+                        .collect();
+
+                    if !additive.is_empty() {
+                        result.push(SqlMigrationStep::AlterTable(AlterTable {
+                            table: alter_table.table.clone(),
+                            changes: additive.clone(),
+                        }));
+
+                        // The diff engine models a column rename as a drop+add pair in the
+                        // same `AlterTable`, so pair each added column with the dropped column
+                        // at the same position rather than broadcasting one dropped column to
+                        // every added column — an `AlterTable` can carry an unrelated rename
+                        // and a brand-new column at once, and those must not get mirrored
+                        // against each other. Extra added columns beyond the number of dropped
+                        // ones are brand new and have nothing to mirror, just backfill.
+                        let new_columns: Vec<String> = additive
+                            .iter()
+                            .filter_map(|change| match change {
+                                TableChange::AddColumn(add_column) => Some(add_column.column.name.clone()),
+                                _ => None,
+                            })
+                            .collect();
+                        let old_columns: Vec<String> = alter_table
+                            .changes
+                            .iter()
+                            .filter_map(|change| match change {
+                                TableChange::DropColumn(drop_column) => Some(drop_column.name.clone()),
+                                _ => None,
+                            })
+                            .collect();
+                        let column_pairs: Vec<(String, Option<String>)> = new_columns
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, new_column)| (new_column, old_columns.get(i).cloned()))
+                            .collect();
+
+                        result.extend(Self::install_routing_trigger(&alter_table.table, &column_pairs));
+                        result.push(SqlMigrationStep::RunBackfill(RunBackfill {
+                            table: alter_table.table.clone(),
+                            batch_size: 1000,
+                        }));
+                    }
+                    // DropColumn/AlterColumn are destructive and wait for the contract phase
+                }
+                x => result.push(x),
+            }
+        }
+
+        result
+    }
+
+    fn contract(steps: Vec<SqlMigrationStep>) -> Vec<SqlMigrationStep> {
+        let mut result = Vec::new();
+
+        for step in steps {
+            match step {
+                SqlMigrationStep::AlterTable(ref alter_table) if !alter_table.changes.is_empty() => {
+                    let destructive: Vec<TableChange> = alter_table
+                        .changes
+                        .iter()
+                        .filter(|change| match change {
+                            TableChange::DropColumn(_) | TableChange::AlterColumn(_) => true,
+                            TableChange::AddColumn(_) => false,
+                        })
+                        .cloned()
+                        .collect();
+
+                    if !destructive.is_empty() {
+                        result.extend(Self::drop_routing_trigger(&alter_table.table));
+                        result.push(SqlMigrationStep::AlterTable(AlterTable {
+                            table: alter_table.table.clone(),
+                            changes: destructive,
+                        }));
+                    }
+                }
+                x => result.push(x),
+            }
+        }
+
+        result.push(Self::drop_schema_routing_helper());
+        result
+    }
+
+    /// `is_old_schema()` lets a routing trigger tell whether the write that
+    /// fired it came from a client still on the old schema, by reading the
+    /// `reshape.is_old_schema` session setting a batched backfill sets
+    /// before it runs so it doesn't fight its own trigger.
+    fn install_schema_routing_helper() -> SqlMigrationStep {
+        SqlMigrationStep::RawSql(format!(
+            "CREATE FUNCTION is_old_schema() RETURNS boolean AS $$ \
+             SELECT coalesce(current_setting('{}', true), 'false') = 'true'; \
+             $$ LANGUAGE sql STABLE;",
+            OLD_SCHEMA_SESSION_VAR
+        ))
+    }
+
+    fn drop_schema_routing_helper() -> SqlMigrationStep {
+        SqlMigrationStep::RawSql("DROP FUNCTION IF EXISTS is_old_schema();".to_string())
+    }
+
+    /// Creates the per-table trigger function that mirrors a write into each
+    /// `(new_column, old_column)` pair back onto its own `old_column` (and
+    /// vice versa), branching on `is_old_schema()` so a client on either
+    /// application version sees a consistent row, then installs the trigger
+    /// that calls it. A pair whose `old_column` is `None` is a brand new
+    /// column with nothing to mirror against; if no pair has an
+    /// `old_column`, the function body is just `NULL;` and the trigger is
+    /// installed all the same. Each call emits a plain `CREATE FUNCTION` and
+    /// `CREATE TRIGGER`; an existing function or trigger of the same name is
+    /// not replaced or reused.
+    fn install_routing_trigger(table: &str, column_pairs: &[(String, Option<String>)]) -> Vec<SqlMigrationStep> {
+        let trigger_name = Self::trigger_name(table);
+        let function_name = Self::mirror_function_name(table);
+
+        let mirror_lines: Vec<String> = column_pairs
+            .iter()
+            .filter_map(|(new_column, old_column)| {
+                old_column.as_ref().map(|old_column| {
+                    format!(
+                        r#"IF is_old_schema() THEN NEW."{new_column}" := NEW."{old_column}"; ELSE NEW."{old_column}" := NEW."{new_column}"; END IF;"#,
+                        new_column = new_column,
+                        old_column = old_column
+                    )
+                })
+            })
+            .collect();
+        let mirror_body = if mirror_lines.is_empty() {
+            "NULL;".to_string()
+        } else {
+            mirror_lines.join(" ")
+        };
+
+        vec![
+            SqlMigrationStep::RawSql(format!(
+                r#"CREATE FUNCTION "{function}"() RETURNS trigger AS $$
+                   BEGIN
+                       {body}
+                       RETURN NEW;
+                   END;
+                   $$ LANGUAGE plpgsql;"#,
+                function = function_name,
+                body = mirror_body
+            )),
+            SqlMigrationStep::CreateTrigger(CreateTrigger {
+                name: trigger_name.clone(),
+                table: table.to_string(),
+                definition: format!(
+                    r#"CREATE TRIGGER "{name}" BEFORE INSERT OR UPDATE ON "{table}"
+                       FOR EACH ROW EXECUTE PROCEDURE "{function}"();"#,
+                    name = trigger_name,
+                    table = table,
+                    function = function_name
+                ),
+            }),
+        ]
+    }
+
+    fn drop_routing_trigger(table: &str) -> Vec<SqlMigrationStep> {
+        vec![
+            SqlMigrationStep::DropTrigger(DropTrigger {
+                name: Self::trigger_name(table),
+                table: table.to_string(),
+            }),
+            SqlMigrationStep::RawSql(format!(r#"DROP FUNCTION IF EXISTS "{}"();"#, Self::mirror_function_name(table))),
+        ]
+    }
+
+    fn trigger_name(table: &str) -> String {
+        format!("reshape_route_{}", table)
+    }
+
+    fn mirror_function_name(table: &str) -> String {
+        format!("reshape_mirror_{}", table)
+    }
+}
+
+/// Which half of a two-step zero-downtime rollout to infer. See
+/// [`SqlDatabaseMigrationStepsInferrer::infer_zero_downtime`].
+pub enum ZeroDowntimePhase {
+    Expand,
+    Contract,
 }
 
+/// The session setting `is_old_schema()` reads to decide whether a writing
+/// client is still on the old schema shape.
+pub const OLD_SCHEMA_SESSION_VAR: &str = "reshape.is_old_schema";
+
 pub fn wrap_as_step<T, F>(steps: Vec<T>, mut wrap_fn: F) -> Vec<SqlMigrationStep>
 where
     F: FnMut(T) -> SqlMigrationStep,
 {
     steps.into_iter().map(|x| wrap_fn(x)).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(sql: &str) -> SqlMigrationStep {
+        SqlMigrationStep::RawSql(sql.to_string())
+    }
+
+    #[test]
+    fn foreign_keys_off_runs_before_the_transaction() {
+        let (before, _, after) = SqlDatabaseMigrationStepsInferrer::partition_around_transaction(vec![
+            raw("PRAGMA foreign_keys=OFF;"),
+            raw("PRAGMA foreign_keys=ON;"),
+        ]);
+
+        assert_eq!(before, vec![raw("PRAGMA foreign_keys=OFF;")]);
+        assert_eq!(after, vec![raw("PRAGMA foreign_keys=ON;")]);
+    }
+
+    #[test]
+    fn the_rebuild_between_the_two_pragmas_stays_inside_the_transaction() {
+        let (before, inside, after) = SqlDatabaseMigrationStepsInferrer::partition_around_transaction(vec![
+            raw("PRAGMA foreign_keys=OFF;"),
+            raw("INSERT INTO new_foo (a) SELECT a FROM foo"),
+            raw("PRAGMA foreign_keys=ON;"),
+        ]);
+
+        assert_eq!(before.len(), 1);
+        assert_eq!(inside, vec![raw("INSERT INTO new_foo (a) SELECT a FROM foo")]);
+        assert_eq!(after.len(), 1);
+    }
+
+    // `expand`/`contract` themselves take a `Vec<SqlMigrationStep>` diff built from
+    // `AlterTable`/`TableChange` values whose struct shapes live in `migration_connector`,
+    // outside this crate's source in this tree — there's nothing here to construct a
+    // realistic diff with. `install_routing_trigger` turns the `(new, old)` column
+    // pairs that `expand` builds into mirror statements, and it only takes plain
+    // strings, so it's tested directly instead.
+
+    fn mirror_body(steps: &[SqlMigrationStep]) -> &str {
+        match steps.get(0) {
+            Some(SqlMigrationStep::RawSql(sql)) => sql,
+            other => panic!("expected the mirror function's RawSql step first, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn each_added_column_mirrors_only_its_own_paired_dropped_column() {
+        // A rename (old_a -> new_a) and a brand-new column (new_b) landing in the same
+        // AlterTable must not cross-wire new_b against old_a.
+        let steps = SqlDatabaseMigrationStepsInferrer::install_routing_trigger(
+            "my_table",
+            &[
+                ("new_a".to_string(), Some("old_a".to_string())),
+                ("new_b".to_string(), None),
+            ],
+        );
+        let body = mirror_body(&steps);
+
+        assert!(body.contains(r#"NEW."new_a" := NEW."old_a""#));
+        assert!(body.contains(r#"NEW."old_a" := NEW."new_a""#));
+        assert!(!body.contains("new_b"));
+        assert!(!body.contains(r#""old_a" := NEW."new_b""#));
+    }
+
+    #[test]
+    fn a_trigger_with_no_paired_old_columns_has_nothing_to_mirror() {
+        let steps = SqlDatabaseMigrationStepsInferrer::install_routing_trigger(
+            "my_table",
+            &[("brand_new".to_string(), None)],
+        );
+
+        assert!(mirror_body(&steps).contains("NULL;"));
+        assert!(!mirror_body(&steps).contains("brand_new"));
+    }
+
+    #[test]
+    fn drop_routing_trigger_drops_both_the_trigger_and_its_function() {
+        let steps = SqlDatabaseMigrationStepsInferrer::drop_routing_trigger("my_table");
+
+        assert!(matches!(steps.get(0), Some(SqlMigrationStep::DropTrigger(_))));
+        match steps.get(1) {
+            Some(SqlMigrationStep::RawSql(sql)) => assert!(sql.contains("reshape_mirror_my_table")),
+            other => panic!("expected a RawSql DROP FUNCTION step, got {:?}", other),
+        }
+    }
+}
@@ -14,6 +14,12 @@ where
     T: TransactionalExt + SqlCapabilities,
 {
     pub executor: T,
+    /// Every schema/namespace this database is configured to manage, beyond
+    /// the one in the connection's search path. Not consumed anywhere yet —
+    /// `WriteQueryBuilder::truncate_tables` (query_builder.rs, not part of
+    /// this tree) would need a matching parameter before `ResetData` could
+    /// actually truncate across all of them.
+    pub namespaces: Vec<String>,
 }
 
 impl<T> SqlDatabase<T>
@@ -21,7 +27,14 @@ where
     T: TransactionalExt + SqlCapabilities,
 {
     pub fn new(executor: T) -> Self {
-        Self { executor }
+        Self {
+            executor,
+            namespaces: Vec::new(),
+        }
+    }
+
+    pub fn with_namespaces(executor: T, namespaces: Vec<String>) -> Self {
+        Self { executor, namespaces }
     }
 }
 
@@ -81,6 +81,14 @@ where
                     })
                 }
                 RootWriteQuery::ResetData(ref rd) => {
+                    // `truncate_tables` only ever lists tables owned by `internal_data_model`,
+                    // so the `_migrations` bookkeeping table is never part of the result and
+                    // survives a reset.
+                    //
+                    // This only truncates the connection's current schema, not every schema in
+                    // `self.namespaces` — `truncate_tables` (query_builder.rs) has no namespace
+                    // parameter to scope its table list by, and isn't part of this tree to add
+                    // one to.
                     let tables = WriteQueryBuilder::truncate_tables(Arc::clone(&rd.internal_data_model));
                     conn.empty_tables(tables)?;
 